@@ -1,4 +1,6 @@
 use std::io::{self, Chars, BufReader, Read};
+use std::collections::VecDeque;
+use std::str::CharIndices;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
@@ -28,6 +30,35 @@ impl Position {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IndentLevel {
+    tabs: usize,
+    spaces: usize,
+}
+
+impl IndentLevel {
+    // Decisive only when tabs and spaces move in the same direction (or one
+    // of them doesn't move at all); `Err` means the two levels can't be
+    // ordered without knowing a tab width.
+    fn compare(&self, other: &IndentLevel) -> Result<::std::cmp::Ordering, ()> {
+        use std::cmp::Ordering::*;
+
+        if self.tabs == other.tabs {
+            return Ok(self.spaces.cmp(&other.spaces))
+        }
+
+        let tabs_ord = self.tabs.cmp(&other.tabs);
+        match (tabs_ord, self.spaces.cmp(&other.spaces)) {
+            (Greater, Less) | (Less, Greater) => Err(()),
+            _ => Ok(tabs_ord),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.tabs + self.spaces
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Lexeme {
     Lparren,
@@ -64,6 +95,8 @@ pub enum Lexeme {
     Unindent(usize),
     Newline,
     Eof,
+
+    Error(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -77,8 +110,14 @@ pub enum LexerError {
     NotUtf8,
     IoError(io::Error),
     UnexpectedEof(Position),
-    InvalidInteger(Position),
+    // Carries the literal text already scanned before the bad character,
+    // so `next_token_lossy` can report what was actually read instead of
+    // just the tail past the error.
+    InvalidInteger(Position, String),
     MysteriousChar(Position),
+    MalformedEscape(Position),
+    MalformedChar(Position),
+    TabError(Position),
 }
 
 impl ::std::convert::From<io::CharsError> for LexerError {
@@ -128,6 +167,20 @@ fn is_hex_digit(d: char) -> bool {
     }
 }
 
+fn is_octal_digit(d: char) -> bool {
+    match d {
+        '0' ... '7' => true,
+        _ => false,
+    }
+}
+
+fn is_binary_digit(d: char) -> bool {
+    match d {
+        '0' | '1' => true,
+        _ => false,
+    }
+}
+
 fn is_letter(l: char) -> bool {
     match l {
         'a' ... 'z'
@@ -153,7 +206,9 @@ fn is_special(s: char) -> bool {
 pub struct Lexer<R: Read> {
     char_iter: Chars<BufReader<R>>,
     cursor: (Option<char>, Option<char>),
-    indent: usize,
+    indents: Vec<IndentLevel>,
+    pending: VecDeque<Token>,
+    nesting: usize,
     seek: usize,
     line: usize,
     column: usize,
@@ -169,7 +224,9 @@ impl<R: Read> Lexer<R> {
         Ok(Lexer {
             char_iter,
             cursor: (c, la),
-            indent: 0,
+            indents: vec![IndentLevel { tabs: 0, spaces: 0 }],
+            pending: VecDeque::new(),
+            nesting: 0,
             seek: 0,
             line: 0,
             column: 0,
@@ -228,18 +285,21 @@ impl<R: Read> Lexer<R> {
         let is_line_start = self.column == 0;
         let start_pos = self.cursor_point();
 
-        let mut indent = 0;
+        let mut tabs = 0;
+        let mut spaces = 0;
         loop {
-            let c = self.cursor;
-            match c.0 {
-                Some(w) if is_whitespace(w) => {
-                    indent += 1
-                },
-                _ => break
+            match self.cursor.0 {
+                Some('\t') => tabs += 1,
+                Some(' ') => spaces += 1,
+                _ => break,
             }
             self.shift()?
         }
 
+        if self.nesting > 0 {
+            return Ok(None)
+        }
+
         if is_line_start && self.cursor.0 != Some('\n') {
             let position = Position {
                 from: start_pos,
@@ -247,21 +307,46 @@ impl<R: Read> Lexer<R> {
             };
 
             use std::cmp::Ordering::*;
-            let res = match indent.cmp(&self.indent) {
-                Greater => Some(Token {
-                    position,
-                    lexeme: Lexeme::Indent(indent - self.indent),
-                }),
-                Less => Some(Token {
-                    position,
-                    lexeme: Lexeme::Unindent(self.indent - indent),
-                }),
-                Equal => None,
-
+            let level = IndentLevel { tabs, spaces };
+            let top = *self.indents.last().unwrap();
+
+            let res = match level.compare(&top) {
+                Err(()) => return Err(LexerError::TabError(position)),
+                Ok(Equal) => None,
+                Ok(Greater) => {
+                    self.indents.push(level);
+                    Some(Token {
+                        position,
+                        lexeme: Lexeme::Indent(level.width() - top.width()),
+                    })
+                },
+                Ok(Less) => {
+                    let mut first = None;
+                    loop {
+                        let popped = *self.indents.last().unwrap();
+                        match level.compare(&popped) {
+                            Ok(Equal) => break,
+                            Ok(Less) => {
+                                self.indents.pop();
+                                let new_top = *self.indents.last().unwrap();
+                                let tok = Token {
+                                    position,
+                                    lexeme: Lexeme::Unindent(popped.width() - new_top.width()),
+                                };
+                                match first {
+                                    None => first = Some(tok),
+                                    Some(_) => self.pending.push_back(tok),
+                                }
+                            },
+                            Ok(Greater) | Err(()) => {
+                                return Err(LexerError::TabError(position))
+                            },
+                        }
+                    }
+                    first
+                },
             };
 
-            self.indent = indent;
-
             Ok(res)
         } else {
             Ok(None)
@@ -269,42 +354,116 @@ impl<R: Read> Lexer<R> {
     }
 
     pub fn next_token(&mut self) -> Result<Token, LexerError> {
-        if let Some(tok) = self.skip_whitespace()? {
+        loop {
+            if let Some(tok) = self.pending.pop_front() {
+                return Ok(tok)
+            }
+
+            if let Some(tok) = self.skip_whitespace()? {
+                return Ok(tok)
+            }
+
+            let (c, la) = self.cursor;
+            let c = match c {
+                Some(c) => c,
+                None => return Ok(self.point_token(Lexeme::Eof)),
+            };
+
+            if c == '\n' && self.nesting > 0 {
+                self.shift()?;
+                continue
+            }
+
+            use self::Lexeme::*;
+            // Sub-lexers that fail leave the cursor sitting on the
+            // offending character (the resync point `next_token_lossy`
+            // needs); don't shift past it here, or lossy mode loses the
+            // very character it's supposed to resynchronize from.
+            let tok = match c {
+                '-' if la == Some('-') => self.comment(),
+                '[' => Ok(self.point_token(Lbracket)),
+                'T' if la == Some('[') => self.t_bracket(),
+                ']' => Ok(self.point_token(Rbracket)),
+                '{' => Ok(self.point_token(Lbrace)),
+                '}' => Ok(self.point_token(Rbrace)),
+                '(' => Ok(self.point_token(Lparren)),
+                ')' => Ok(self.point_token(Rparren)),
+                '\n' => Ok(self.point_token(Newline)),
+                '"' => self.string(),
+                '\'' => self.char(),
+                '0' if la == Some('x') => self.radix_integer("0x", is_hex_digit),
+                '0' if la == Some('o') => self.radix_integer("0o", is_octal_digit),
+                '0' if la == Some('b') => self.radix_integer("0b", is_binary_digit),
+                s if is_special(s) => self.operator(),
+                l if is_letter(l) => self.word(),
+                d if is_digit(d) => self.integer_or_word(),
+                _ => {
+                    let pos = self.cursor_position();
+                    return Err(LexerError::MysteriousChar(pos));
+                },
+            }?;
+
+            self.shift()?;
+
+            match tok.lexeme {
+                Lparren | Lbracket | Lbrace | Tbracket => self.nesting += 1,
+                Rparren | Rbracket | Rbrace => self.nesting = self.nesting.saturating_sub(1),
+                _ => (),
+            }
+
             return Ok(tok)
         }
+    }
 
-        let (c, la) = self.cursor;
-        let c = match c {
-            Some(c) => c,
-            None => return Ok(self.point_token(Lexeme::Eof)),
-        };
+    // Like `next_token`, but never fails: a bad byte or unterminated literal
+    // is resynchronized past and reported as a `Lexeme::Error` token instead
+    // of aborting the whole stream, so IDE tooling can keep tokenizing.
+    pub fn next_token_lossy(&mut self) -> Token {
+        let start_pos = self.cursor_point();
 
-        use self::Lexeme::*;
-        let tok = match c {
-            '-' if la == Some('-') => self.comment(),
-            '[' => Ok(self.point_token(Lbracket)),
-            'T' if la == Some('[') => self.t_bracket(),
-            ']' => Ok(self.point_token(Rbracket)),
-            '{' => Ok(self.point_token(Lbrace)),
-            '}' => Ok(self.point_token(Rbrace)),
-            '(' => Ok(self.point_token(Lparren)),
-            ')' => Ok(self.point_token(Rparren)),
-            '\n' => Ok(self.point_token(Newline)),
-            '"' => self.string(),
-            '\'' => self.char(),
-            '0' if la == Some('x') => self.hex_integer(),
-            s if is_special(s) => self.operator(),
-            l if is_letter(l) => self.word(),
-            d if is_digit(d) => self.integer_or_word(),
-            _ => {
-                let pos = self.cursor_position();
-                return Err(LexerError::MysteriousChar(pos));
-            },
-        };
+        match self.next_token() {
+            Ok(tok) => tok,
+            Err(LexerError::MysteriousChar(pos)) => {
+                let mut raw = String::new();
+                if let Some(c) = self.cursor.0 {
+                    raw.push(c);
+                }
+                let _ = self.shift();
 
-        self.shift()?;
+                Token { lexeme: Lexeme::Error(raw), position: pos }
+            },
+            Err(LexerError::UnexpectedEof(pos)) => {
+                let position = Position { from: start_pos, to: pos.to };
+                Token { lexeme: Lexeme::Error("unterminated string".to_string()), position }
+            },
+            Err(LexerError::InvalidInteger(_, partial)) => {
+                let raw = partial + &self.resync_to_whitespace();
+                let position = Position { from: start_pos, to: self.cursor_point() };
+                Token { lexeme: Lexeme::Error(raw), position }
+            },
+            Err(_other) => {
+                let raw = self.resync_to_whitespace();
+                let position = Position { from: start_pos, to: self.cursor_point() };
+                Token { lexeme: Lexeme::Error(raw), position }
+            },
+        }
+    }
 
-        tok
+    // Consume characters up to (not including) the next whitespace, newline
+    // or end of input, returning the consumed run. Used by `next_token_lossy`
+    // to resynchronize after an error in the middle of a token.
+    fn resync_to_whitespace(&mut self) -> String {
+        let mut raw = String::new();
+        while let Some(c) = self.cursor.0 {
+            if is_whitespace(c) || c == '\n' {
+                break
+            }
+            raw.push(c);
+            if self.shift().is_err() {
+                break
+            }
+        }
+        raw
     }
 
     fn read_word(&mut self) -> Result<String, LexerError> {
@@ -351,16 +510,82 @@ impl<R: Read> Lexer<R> {
         })
     }
 
+    // Appends the digit run at the cursor into `out`, treating `_` as a
+    // digit separator between digits and stripping it from the stored text.
+    // A trailing separator with no digit after it is left in `out` instead
+    // (it's still consumed into the token's position span by the caller's
+    // final shift, so leaving it out of the text would desync the two).
+    // Assumes self.cursor.0 is already known to be a digit.
+    fn read_digits(&mut self, out: &mut String) -> Result<(), LexerError> {
+        loop {
+            match self.cursor.0 {
+                Some(d) if is_digit(d) => out.push(d),
+                Some('_') => (),
+                _ => break,
+            }
+
+            match self.cursor.1 {
+                Some(c) if is_digit(c) || c == '_' => self.shift()?,
+                _ => {
+                    if self.cursor.0 == Some('_') {
+                        out.push('_');
+                    }
+                    break
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    // Appends an `[eE][+-]?digits` exponent suffix to `out`, assuming
+    // self.cursor.1 is known to be 'e' or 'E'. Shifts past `e`/the sign as
+    // soon as each is recorded, so on error the cursor sits on the first
+    // character not already accounted for in `out` rather than on top of
+    // the last one pushed.
+    fn read_exponent(&mut self, out: &mut String) -> Result<(), LexerError> {
+        let start_pos = self.cursor_point();
+        self.shift()?;
+        out.push(self.cursor.0.unwrap());
+
+        match self.cursor.1 {
+            Some(sign @ '+') | Some(sign @ '-') => {
+                self.shift()?;
+                out.push(sign);
+                self.shift()?;
+            },
+            _ => {
+                self.shift()?;
+            },
+        }
+
+        if !self.cursor.0.map(is_digit).unwrap_or(false) {
+            let pos = Position { from: start_pos, to: self.upper_bound() };
+            return Err(LexerError::InvalidInteger(pos, out.clone()))
+        }
+
+        self.read_digits(out)
+    }
+
     fn integer_or_word(&mut self) -> Result<Token, LexerError> {
         let start_pos = self.cursor_point();
 
         let mut thing = String::new();
         let lexeme;
         loop {
-            thing.push(self.cursor.0.unwrap());
+            match self.cursor.0 {
+                Some('_') => (),
+                Some(d) => thing.push(d),
+                None => unreachable!(),
+            }
 
             match self.cursor.1 {
-                Some(d) if is_digit(d) => (),
+                Some(d) if is_digit(d) || d == '_' => (),
+                Some('e') | Some('E') => {
+                    self.read_exponent(&mut thing)?;
+                    lexeme = Lexeme::Float(thing);
+                    break
+                },
                 Some(l) if is_letter(l) => {
                     let rem = self.read_word()?;
                     thing.push_str(&rem);
@@ -384,26 +609,35 @@ impl<R: Read> Lexer<R> {
                     })
                 },
                 Some('.') => {
-                    thing.push('.');
                     self.shift()?;
 
-                    if !self.cursor.0.map(is_digit).unwrap_or(false) {
+                    if !self.cursor.1.map(is_digit).unwrap_or(false) {
                         let pos = Position {
                             from: start_pos,
                             to: self.upper_bound(),
                         };
-                        return Err(LexerError::InvalidInteger(pos))
+                        return Err(LexerError::InvalidInteger(pos, thing))
                     }
 
-                    while self.cursor.1.map(is_digit).unwrap_or(false) {
-                        thing.push(self.cursor.0.unwrap());
-                        self.shift()?;
+                    thing.push('.');
+                    self.shift()?;
+                    self.read_digits(&mut thing)?;
+
+                    match self.cursor.1 {
+                        Some('e') | Some('E') => self.read_exponent(&mut thing)?,
+                        _ => {},
                     }
 
                     lexeme = Lexeme::Float(thing);
                     break
                 },
                 _ => {
+                    // A trailing separator with no digit after it is still
+                    // covered by the token's span (`upper_bound` below), so
+                    // keep it in the text too (see `read_digits`).
+                    if self.cursor.0 == Some('_') {
+                        thing.push('_');
+                    }
                     lexeme = Lexeme::Integer(thing);
                     break
                 },
@@ -466,24 +700,18 @@ impl<R: Read> Lexer<R> {
 
         let mut string = String::new();
         loop {
-            match self.cursor {
-                (Some('\\'), Some('"')) => {
-                    string.push('"');
-                    self.shift()?;
-                },
-                (Some('\\'), Some('\\')) => {
-                    string.push('\\');
+            match self.cursor.0 {
+                Some('\\') => string.push(self.read_escape()?),
+                Some('"') => break,
+                Some(c) => {
+                    string.push(c);
                     self.shift()?;
                 },
-                (Some('"'), _) => break,
-                (Some(c), _) => string.push(c),
-                (None, _) => {
+                None => {
                     let pos = self.cursor_position();
                     return Err(LexerError::UnexpectedEof(pos))
                 },
             }
-
-            self.shift()?;
         }
 
         let position = Position {
@@ -498,29 +726,152 @@ impl<R: Read> Lexer<R> {
     }
 
     fn char(&mut self) -> Result<Token, LexerError> {
-        unimplemented!()
+        let start_pos = self.cursor_point();
+        self.shift()?;
+
+        let c = match self.cursor.0 {
+            Some('\\') => self.read_escape()?,
+            Some('\'') | None => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(LexerError::MalformedChar(pos))
+            },
+            Some(c) => {
+                self.shift()?;
+                c
+            },
+        };
+
+        match self.cursor.0 {
+            Some('\'') => (),
+            _ => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(LexerError::MalformedChar(pos))
+            },
+        }
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(Token {
+            position,
+            lexeme: Lexeme::Char(c),
+        })
     }
 
-    fn hex_integer(&mut self) -> Result<Token, LexerError> {
+    // Shared by `string` and `char`: cursor sits on the leading `\\` of an
+    // escape sequence, and on return sits on the first character following it.
+    fn read_escape(&mut self) -> Result<char, LexerError> {
         let start_pos = self.cursor_point();
         self.shift()?;
-        self.shift()?;
 
-        let mut number = "0x".to_string();
+        let c = match self.cursor.0 {
+            Some('n') => { self.shift()?; '\n' },
+            Some('t') => { self.shift()?; '\t' },
+            Some('r') => { self.shift()?; '\r' },
+            Some('0') => { self.shift()?; '\0' },
+            Some('\\') => { self.shift()?; '\\' },
+            Some('\'') => { self.shift()?; '\'' },
+            Some('"') => { self.shift()?; '"' },
+            Some('x') => {
+                self.shift()?;
+
+                let mut value: u32 = 0;
+                for _ in 0..2 {
+                    match self.cursor.0 {
+                        Some(d) if is_hex_digit(d) => {
+                            value = value * 16 + d.to_digit(16).unwrap();
+                            self.shift()?;
+                        },
+                        _ => {
+                            let pos = Position { from: start_pos, to: self.upper_bound() };
+                            return Err(LexerError::MalformedEscape(pos))
+                        },
+                    }
+                }
+
+                value as u8 as char
+            },
+            Some('u') => {
+                self.shift()?;
+
+                match self.cursor.0 {
+                    Some('{') => self.shift()?,
+                    _ => {
+                        let pos = Position { from: start_pos, to: self.upper_bound() };
+                        return Err(LexerError::MalformedEscape(pos))
+                    },
+                }
+
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                loop {
+                    match self.cursor.0 {
+                        Some(d) if is_hex_digit(d) && digits < 6 => {
+                            value = value * 16 + d.to_digit(16).unwrap();
+                            digits += 1;
+                            self.shift()?;
+                        },
+                        Some('}') if digits > 0 => {
+                            self.shift()?;
+                            break
+                        },
+                        _ => {
+                            let pos = Position { from: start_pos, to: self.upper_bound() };
+                            return Err(LexerError::MalformedEscape(pos))
+                        },
+                    }
+                }
+
+                match ::std::char::from_u32(value) {
+                    Some(c) => c,
+                    None => {
+                        let pos = Position { from: start_pos, to: self.upper_bound() };
+                        return Err(LexerError::MalformedEscape(pos))
+                    },
+                }
+            },
+            _ => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(LexerError::MalformedEscape(pos))
+            },
+        };
+
+        Ok(c)
+    }
+
+    // Lexes `0x`/`0o`/`0b`-prefixed integers, sharing the `_` digit-separator
+    // rule with `integer_or_word`. Called with the cursor on the leading `0`.
+    fn radix_integer(&mut self, prefix: &str, is_radix_digit: fn(char) -> bool) -> Result<Token, LexerError> {
+        let start_pos = self.cursor_point();
         self.shift()?;
+        self.shift()?;
+
+        let mut number = prefix.to_string();
         loop {
             let eon = self.cursor.1
                 .map(|w| is_whitespace(w) || w == '\n')
                 .unwrap_or(true);
 
             match self.cursor.0 {
-                Some(d) if is_hex_digit(d) =>{
-                    number.push(d)
+                Some(d) if is_radix_digit(d) => number.push(d),
+                Some('_') => (),
+                _ => {
+                    let pos = self.cursor_position();
+                    return Err(LexerError::InvalidInteger(pos, number))
                 },
-                _ => unimplemented!()
             }
 
-            if eon { break }
+            if eon {
+                // A trailing separator with no digit after it is still
+                // covered by the token's span (`upper_bound` below), so
+                // keep it in the text too (see `read_digits`).
+                if self.cursor.0 == Some('_') {
+                    number.push('_');
+                }
+                break
+            }
             self.shift()?;
         }
 
@@ -582,57 +933,771 @@ impl<R: Read> Lexer<R> {
     }
 }
 
-//#[cfg(test)]
-mod tests {
-    use super::Lexeme;
-    use super::{Lexer, LexerError};
+// Zero-copy counterpart to `Lexeme`/`Token`: variants that don't need
+// escape-decoding borrow straight from the source instead of allocating.
+// `String` and `Char` still carry owned/decoded payloads since their text
+// can differ from the source bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SliceLexeme<'src> {
+    Lparren,
+    Rparren,
+    Lbracket,
+    Tbracket,
+    Rbracket,
+    Lbrace,
+    Rbrace,
 
-    fn collect_lexemes<R: ::std::io::Read>(lexer: &mut Lexer<R>) -> Vec<super::Lexeme> {
-        let mut lexemes = vec![];
-        loop {
-            let tok = lexer.next_token().unwrap();
+    Keyword(&'src str),
+    Word(&'src str),
+    Mword(&'src str),
+    Underscore,
+    Comma,
+    Dot,
+    Rarrow,
+    Colon,
+    Bar,
+    Equals,
 
-            if tok.lexeme == Lexeme::Eof {
-                lexemes.push(Lexeme::Eof);
-                break
-            }
+    Operator(&'src str),
 
-            lexemes.push(tok.lexeme)
-        }
-        lexemes
-    }
+    String(String),
+    Char(char),
+    Integer(&'src str),
+    Float(&'src str),
 
-    fn collect_positions<R: ::std::io::Read>(lexer: &mut Lexer<R>) -> Vec<super::Position> {
-        let mut positions = vec![];
-        loop {
-            let tok = lexer.next_token().unwrap();
+    Comment(&'src str),
+    DocComment(&'src str),
+    TopDocComment(&'src str),
 
-            if tok.lexeme == Lexeme::Eof {
-                break
-            }
+    Indent(usize),
+    Unindent(usize),
+    Newline,
+    Eof,
+}
 
-            positions.push(tok.position)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceToken<'src> {
+    pub lexeme: SliceLexeme<'src>,
+    pub position: Position,
+}
+
+#[derive(Debug)]
+pub enum SliceLexerError {
+    UnexpectedEof(Position),
+    InvalidInteger(Position),
+    MysteriousChar(Position),
+    MalformedEscape(Position),
+    MalformedChar(Position),
+    TabError(Position),
+}
+
+// Cursor-based lexer over a whole `&'src str`, as opposed to `Lexer`'s
+// incremental `Read` stream. Since the entire input is already in memory,
+// `Word`/`Operator`/`Comment`/etc. are recorded as start/end byte offsets
+// and sliced out of `src` rather than built up char by char into a fresh
+// `String`. Note this means `Integer`/`Float` text keeps any `_` digit
+// separators verbatim, unlike `Lexer`, which strips them while copying.
+#[derive(Debug)]
+pub struct SliceLexer<'src> {
+    src: &'src str,
+    chars: CharIndices<'src>,
+    cursor: (Option<(usize, char)>, Option<(usize, char)>),
+    indents: Vec<IndentLevel>,
+    pending: VecDeque<SliceToken<'src>>,
+    nesting: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'src> SliceLexer<'src> {
+    pub fn new(src: &'src str) -> Self {
+        let mut chars = src.char_indices();
+        let c = chars.next();
+        let la = chars.next();
+
+        SliceLexer {
+            src,
+            chars,
+            cursor: (c, la),
+            indents: vec![IndentLevel { tabs: 0, spaces: 0 }],
+            pending: VecDeque::new(),
+            nesting: 0,
+            line: 0,
+            column: 0,
         }
-        positions
     }
 
-    fn draw_positions(positions: &[super::Position]) -> String {
-        let chars = ['x', 'y'];
+    fn cur0(&self) -> Option<char> {
+        self.cursor.0.map(|(_, c)| c)
+    }
 
-        let mut line = 0;
-        let mut column = 0;
+    fn cur1(&self) -> Option<char> {
+        self.cursor.1.map(|(_, c)| c)
+    }
 
-        let mut string = String::new();
+    fn shift(&mut self) {
+        let (_, la) = self.cursor;
+        let next = self.chars.next();
 
-        for (i, p) in positions.iter().enumerate() {
-            while line < p.from.1 {
-                string.push('\n');
-                line += 1;
-                column = 0;
+        match self.cur0() {
+            Some('\n') => {
+                self.column = 0;
+                self.line += 1;
+            },
+            _ => {
+                self.column += 1;
             }
+        }
 
-            while column < p.from.2 {
-                string.push(' ');
+        self.cursor = (la, next);
+    }
+
+    // Byte offset one past the current cursor character, or the length of
+    // `src` at end of input. Mirrors `Lexer::upper_bound`.
+    fn upper_bound(&self) -> (usize, usize, usize) {
+        let abs = self.cursor.1.map_or(self.src.len(), |(b, _)| b);
+        (abs, self.line, self.column + 1)
+    }
+
+    fn cursor_point(&self) -> (usize, usize, usize) {
+        let abs = self.cursor.0.map_or(self.src.len(), |(b, _)| b);
+        (abs, self.line, self.column)
+    }
+
+    fn cursor_position(&self) -> Position {
+        let (abs, line, column) = self.cursor_point();
+        Position::point(abs, line, column)
+    }
+
+    fn point_token(&self, lex: SliceLexeme<'src>) -> SliceToken<'src> {
+        SliceToken {
+            lexeme: lex,
+            position: self.cursor_position(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<SliceToken<'src>>, SliceLexerError> {
+        let is_line_start = self.column == 0;
+        let start_pos = self.cursor_point();
+
+        let mut tabs = 0;
+        let mut spaces = 0;
+        loop {
+            match self.cur0() {
+                Some('\t') => tabs += 1,
+                Some(' ') => spaces += 1,
+                _ => break,
+            }
+            self.shift()
+        }
+
+        if self.nesting > 0 {
+            return Ok(None)
+        }
+
+        if is_line_start && self.cur0() != Some('\n') {
+            let position = Position {
+                from: start_pos,
+                to: self.cursor_point(),
+            };
+
+            use std::cmp::Ordering::*;
+            let level = IndentLevel { tabs, spaces };
+            let top = *self.indents.last().unwrap();
+
+            let res = match level.compare(&top) {
+                Err(()) => return Err(SliceLexerError::TabError(position)),
+                Ok(Equal) => None,
+                Ok(Greater) => {
+                    self.indents.push(level);
+                    Some(SliceToken {
+                        position,
+                        lexeme: SliceLexeme::Indent(level.width() - top.width()),
+                    })
+                },
+                Ok(Less) => {
+                    let mut first = None;
+                    loop {
+                        let popped = *self.indents.last().unwrap();
+                        match level.compare(&popped) {
+                            Ok(Equal) => break,
+                            Ok(Less) => {
+                                self.indents.pop();
+                                let new_top = *self.indents.last().unwrap();
+                                let tok = SliceToken {
+                                    position,
+                                    lexeme: SliceLexeme::Unindent(popped.width() - new_top.width()),
+                                };
+                                match first {
+                                    None => first = Some(tok),
+                                    Some(_) => self.pending.push_back(tok),
+                                }
+                            },
+                            Ok(Greater) | Err(()) => {
+                                return Err(SliceLexerError::TabError(position))
+                            },
+                        }
+                    }
+                    first
+                },
+            };
+
+            Ok(res)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn next_token(&mut self) -> Result<SliceToken<'src>, SliceLexerError> {
+        loop {
+            if let Some(tok) = self.pending.pop_front() {
+                return Ok(tok)
+            }
+
+            if let Some(tok) = self.skip_whitespace()? {
+                return Ok(tok)
+            }
+
+            let (c, la) = (self.cur0(), self.cur1());
+            let c = match c {
+                Some(c) => c,
+                None => return Ok(self.point_token(SliceLexeme::Eof)),
+            };
+
+            if c == '\n' && self.nesting > 0 {
+                self.shift();
+                continue
+            }
+
+            use self::SliceLexeme::*;
+            // Sub-lexers that fail leave the cursor sitting on the
+            // offending character; don't shift past it here (mirrors
+            // `Lexer::next_token`).
+            let tok = match c {
+                '-' if la == Some('-') => self.comment(),
+                '[' => Ok(self.point_token(Lbracket)),
+                'T' if la == Some('[') => Ok(self.t_bracket()),
+                ']' => Ok(self.point_token(Rbracket)),
+                '{' => Ok(self.point_token(Lbrace)),
+                '}' => Ok(self.point_token(Rbrace)),
+                '(' => Ok(self.point_token(Lparren)),
+                ')' => Ok(self.point_token(Rparren)),
+                '\n' => Ok(self.point_token(Newline)),
+                '"' => self.string(),
+                '\'' => self.char(),
+                '0' if la == Some('x') => self.radix_integer(is_hex_digit),
+                '0' if la == Some('o') => self.radix_integer(is_octal_digit),
+                '0' if la == Some('b') => self.radix_integer(is_binary_digit),
+                s if is_special(s) => Ok(self.operator()),
+                l if is_letter(l) => Ok(self.word()),
+                d if is_digit(d) => self.integer_or_word(),
+                _ => {
+                    let pos = self.cursor_position();
+                    return Err(SliceLexerError::MysteriousChar(pos));
+                },
+            }?;
+
+            self.shift();
+
+            match tok.lexeme {
+                Lparren | Lbracket | Lbrace | Tbracket => self.nesting += 1,
+                Rparren | Rbracket | Rbrace => self.nesting = self.nesting.saturating_sub(1),
+                _ => (),
+            }
+
+            return Ok(tok)
+        }
+    }
+
+    // Advances past the rest of a word/identifier, assuming self.cur0() is
+    // already known to be a letter, and returns the whole run as a slice.
+    fn read_word(&mut self) -> &'src str {
+        let start = self.cursor_point().0;
+        loop {
+            let eow = !self.cur1()
+                .map(|c| is_letter(c) || is_digit(c))
+                .unwrap_or(false);
+
+            if eow { break }
+            self.shift();
+        }
+
+        &self.src[start..self.upper_bound().0]
+    }
+
+    fn word(&mut self) -> SliceToken<'src> {
+        let start_pos = self.cursor_point();
+
+        let word = self.read_word();
+        let is_mword = match self.cur1() {
+            Some('[') => {
+                self.shift();
+                true
+            },
+            _ => false
+        };
+        let lexeme = match word {
+            "_" => SliceLexeme::Underscore,
+            _ if is_mword => SliceLexeme::Mword(word),
+            _ if is_keyword(word) => SliceLexeme::Keyword(word),
+            _ => SliceLexeme::Word(word),
+        };
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        SliceToken {
+            position,
+            lexeme,
+        }
+    }
+
+    // Advances past the digit run at the cursor, treating `_` as a digit
+    // separator. Assumes self.cur0() is already known to be a digit.
+    fn skip_digits(&mut self) {
+        while self.cur1().map(|c| is_digit(c) || c == '_').unwrap_or(false) {
+            self.shift();
+        }
+    }
+
+    // Advances past an `[eE][+-]?digits` exponent suffix, assuming
+    // self.cur1() is known to be 'e' or 'E'.
+    fn skip_exponent(&mut self) -> Result<(), SliceLexerError> {
+        let start_pos = self.cursor_point();
+        self.shift();
+
+        match self.cur1() {
+            Some('+') | Some('-') => self.shift(),
+            _ => {},
+        }
+
+        if !self.cur1().map(is_digit).unwrap_or(false) {
+            let pos = Position { from: start_pos, to: self.upper_bound() };
+            return Err(SliceLexerError::InvalidInteger(pos))
+        }
+
+        self.shift();
+        self.skip_digits();
+        Ok(())
+    }
+
+    fn integer_or_word(&mut self) -> Result<SliceToken<'src>, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        let start = start_pos.0;
+        let lexeme;
+        loop {
+            match self.cur1() {
+                Some(d) if is_digit(d) || d == '_' => (),
+                Some('e') | Some('E') => {
+                    self.skip_exponent()?;
+                    lexeme = SliceLexeme::Float(&self.src[start..self.upper_bound().0]);
+                    break
+                },
+                Some(l) if is_letter(l) => {
+                    self.read_word();
+                    let word = &self.src[start..self.upper_bound().0];
+
+                    let lexeme = match self.cur1() {
+                        Some('[') => {
+                            self.shift();
+                            SliceLexeme::Mword(word)
+                        },
+                        _ => SliceLexeme::Word(word)
+                    };
+
+                    let position = Position {
+                        from: start_pos,
+                        to: self.upper_bound(),
+                    };
+
+                    return Ok(SliceToken {
+                        position,
+                        lexeme,
+                    })
+                },
+                Some('.') => {
+                    self.shift();
+
+                    if !self.cur1().map(is_digit).unwrap_or(false) {
+                        let pos = Position {
+                            from: start_pos,
+                            to: self.upper_bound(),
+                        };
+                        return Err(SliceLexerError::InvalidInteger(pos))
+                    }
+
+                    self.shift();
+                    self.skip_digits();
+
+                    match self.cur1() {
+                        Some('e') | Some('E') => self.skip_exponent()?,
+                        _ => {},
+                    }
+
+                    lexeme = SliceLexeme::Float(&self.src[start..self.upper_bound().0]);
+                    break
+                },
+                _ => {
+                    lexeme = SliceLexeme::Integer(&self.src[start..self.upper_bound().0]);
+                    break
+                },
+            }
+
+            self.shift();
+        }
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(SliceToken {
+            position,
+            lexeme,
+        })
+    }
+
+    fn comment(&mut self) -> Result<SliceToken<'src>, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        self.shift();
+        self.shift();
+
+        let lex = match self.cur0() {
+            Some('.') => {
+                self.shift();
+                SliceLexeme::DocComment
+            },
+            Some('^') => {
+                self.shift();
+                SliceLexeme::TopDocComment
+            },
+            _ => SliceLexeme::Comment,
+        };
+
+        let content_start = self.cursor_point().0;
+        loop {
+            match self.cur0() {
+                Some('\n') | None => break,
+                Some(_) => (),
+            }
+            self.shift();
+        }
+
+        let line = &self.src[content_start..self.cursor_point().0];
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(SliceToken {
+            position,
+            lexeme: lex(line),
+        })
+    }
+
+    fn string(&mut self) -> Result<SliceToken<'src>, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        self.shift();
+
+        let mut string = String::new();
+        loop {
+            match self.cur0() {
+                Some('\\') => string.push(self.read_escape()?),
+                Some('"') => break,
+                Some(c) => {
+                    string.push(c);
+                    self.shift();
+                },
+                None => {
+                    let pos = self.cursor_position();
+                    return Err(SliceLexerError::UnexpectedEof(pos))
+                },
+            }
+        }
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(SliceToken {
+            position,
+            lexeme: SliceLexeme::String(string)
+        })
+    }
+
+    fn char(&mut self) -> Result<SliceToken<'src>, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        self.shift();
+
+        let c = match self.cur0() {
+            Some('\\') => self.read_escape()?,
+            Some('\'') | None => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(SliceLexerError::MalformedChar(pos))
+            },
+            Some(c) => {
+                self.shift();
+                c
+            },
+        };
+
+        match self.cur0() {
+            Some('\'') => (),
+            _ => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(SliceLexerError::MalformedChar(pos))
+            },
+        }
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(SliceToken {
+            position,
+            lexeme: SliceLexeme::Char(c),
+        })
+    }
+
+    // Shared by `string` and `char`: cursor sits on the leading `\\` of an
+    // escape sequence, and on return sits on the first character following it.
+    fn read_escape(&mut self) -> Result<char, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        self.shift();
+
+        let c = match self.cur0() {
+            Some('n') => { self.shift(); '\n' },
+            Some('t') => { self.shift(); '\t' },
+            Some('r') => { self.shift(); '\r' },
+            Some('0') => { self.shift(); '\0' },
+            Some('\\') => { self.shift(); '\\' },
+            Some('\'') => { self.shift(); '\'' },
+            Some('"') => { self.shift(); '"' },
+            Some('x') => {
+                self.shift();
+
+                let mut value: u32 = 0;
+                for _ in 0..2 {
+                    match self.cur0() {
+                        Some(d) if is_hex_digit(d) => {
+                            value = value * 16 + d.to_digit(16).unwrap();
+                            self.shift();
+                        },
+                        _ => {
+                            let pos = Position { from: start_pos, to: self.upper_bound() };
+                            return Err(SliceLexerError::MalformedEscape(pos))
+                        },
+                    }
+                }
+
+                value as u8 as char
+            },
+            Some('u') => {
+                self.shift();
+
+                match self.cur0() {
+                    Some('{') => self.shift(),
+                    _ => {
+                        let pos = Position { from: start_pos, to: self.upper_bound() };
+                        return Err(SliceLexerError::MalformedEscape(pos))
+                    },
+                }
+
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                loop {
+                    match self.cur0() {
+                        Some(d) if is_hex_digit(d) && digits < 6 => {
+                            value = value * 16 + d.to_digit(16).unwrap();
+                            digits += 1;
+                            self.shift();
+                        },
+                        Some('}') if digits > 0 => {
+                            self.shift();
+                            break
+                        },
+                        _ => {
+                            let pos = Position { from: start_pos, to: self.upper_bound() };
+                            return Err(SliceLexerError::MalformedEscape(pos))
+                        },
+                    }
+                }
+
+                match ::std::char::from_u32(value) {
+                    Some(c) => c,
+                    None => {
+                        let pos = Position { from: start_pos, to: self.upper_bound() };
+                        return Err(SliceLexerError::MalformedEscape(pos))
+                    },
+                }
+            },
+            _ => {
+                let pos = Position { from: start_pos, to: self.upper_bound() };
+                return Err(SliceLexerError::MalformedEscape(pos))
+            },
+        };
+
+        Ok(c)
+    }
+
+    // Lexes `0x`/`0o`/`0b`-prefixed integers, sharing the `_` digit-separator
+    // rule with `integer_or_word`. Called with the cursor on the leading `0`;
+    // the radix prefix is part of the source slice, so unlike `Lexer`'s
+    // `radix_integer` there's no separate prefix string to seed.
+    fn radix_integer(&mut self, is_radix_digit: fn(char) -> bool) -> Result<SliceToken<'src>, SliceLexerError> {
+        let start_pos = self.cursor_point();
+        let start = start_pos.0;
+        self.shift();
+        self.shift();
+
+        loop {
+            let eon = self.cur1()
+                .map(|w| is_whitespace(w) || w == '\n')
+                .unwrap_or(true);
+
+            match self.cur0() {
+                Some(d) if is_radix_digit(d) => (),
+                Some('_') => (),
+                _ => {
+                    let pos = self.cursor_position();
+                    return Err(SliceLexerError::InvalidInteger(pos))
+                },
+            }
+
+            if eon { break }
+            self.shift();
+        }
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        Ok(SliceToken {
+            position,
+            lexeme: SliceLexeme::Integer(&self.src[start..self.upper_bound().0]),
+        })
+    }
+
+    fn t_bracket(&mut self) -> SliceToken<'src> {
+        let position = Position {
+            from: self.cursor_point(),
+            to: self.upper_bound(),
+        };
+        self.shift();
+
+        SliceToken {
+            position,
+            lexeme: SliceLexeme::Tbracket,
+        }
+    }
+
+    fn operator(&mut self) -> SliceToken<'src> {
+        let start_pos = self.cursor_point();
+        let start = start_pos.0;
+
+        loop {
+            let eop = !self.cur1().map(is_special).unwrap_or(false);
+            if eop { break }
+            self.shift();
+        }
+
+        let operator = &self.src[start..self.upper_bound().0];
+
+        let position = Position {
+            from: start_pos,
+            to: self.upper_bound(),
+        };
+
+        let lexeme = match operator {
+            "," => SliceLexeme::Comma,
+            "." => SliceLexeme::Dot,
+            "->" => SliceLexeme::Rarrow,
+            ":" => SliceLexeme::Colon,
+            "|" => SliceLexeme::Bar,
+            "=" => SliceLexeme::Equals,
+            _ => SliceLexeme::Operator(operator)
+        };
+
+        SliceToken {
+            position,
+            lexeme,
+        }
+    }
+}
+
+//#[cfg(test)]
+mod tests {
+    use super::Lexeme;
+    use super::{Lexer, LexerError};
+    use super::{SliceLexeme, SliceLexer, SliceLexerError};
+
+    fn collect_slice_lexemes<'src>(lexer: &mut SliceLexer<'src>) -> Vec<SliceLexeme<'src>> {
+        let mut lexemes = vec![];
+        loop {
+            let tok = lexer.next_token().unwrap();
+
+            if tok.lexeme == SliceLexeme::Eof {
+                lexemes.push(SliceLexeme::Eof);
+                break
+            }
+
+            lexemes.push(tok.lexeme)
+        }
+        lexemes
+    }
+
+    fn collect_lexemes<R: ::std::io::Read>(lexer: &mut Lexer<R>) -> Vec<super::Lexeme> {
+        let mut lexemes = vec![];
+        loop {
+            let tok = lexer.next_token().unwrap();
+
+            if tok.lexeme == Lexeme::Eof {
+                lexemes.push(Lexeme::Eof);
+                break
+            }
+
+            lexemes.push(tok.lexeme)
+        }
+        lexemes
+    }
+
+    fn collect_positions<R: ::std::io::Read>(lexer: &mut Lexer<R>) -> Vec<super::Position> {
+        let mut positions = vec![];
+        loop {
+            let tok = lexer.next_token().unwrap();
+
+            if tok.lexeme == Lexeme::Eof {
+                break
+            }
+
+            positions.push(tok.position)
+        }
+        positions
+    }
+
+    fn draw_positions(positions: &[super::Position]) -> String {
+        let chars = ['x', 'y'];
+
+        let mut line = 0;
+        let mut column = 0;
+
+        let mut string = String::new();
+
+        for (i, p) in positions.iter().enumerate() {
+            while line < p.from.1 {
+                string.push('\n');
+                line += 1;
+                column = 0;
+            }
+
+            while column < p.from.2 {
+                string.push(' ');
                 column += 1;
             }
 
@@ -711,4 +1776,309 @@ yyyyxxxxxxxxxxxxxxx yyyyyyyyx
             Unindent(4), Eof
         ])
     }
+
+    #[test] fn chars_and_escapes() {
+        use self::Lexeme::*;
+        let source = concat!(
+            "'a' '\\n' '\\x41' '\\u{1f600}'\n",
+            "\"tab\\there\\x21\\u{263a}\"\n"
+        ).as_bytes();
+
+        let mut lexer = Lexer::new(source).unwrap();
+        let mut lexemes = collect_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Char('a'), Char('\n'), Char('\x41'), Char('\u{1f600}'), Newline,
+            String("tab\there!\u{263a}".to_string()), Newline,
+            Eof
+        ])
+    }
+
+    #[test] fn malformed_char_errors() {
+        let empty = b"''".to_vec();
+        let mut lexer = Lexer::new(&*empty).unwrap();
+        match lexer.next_token() {
+            Err(LexerError::MalformedChar(_)) => (),
+            other => panic!("expected MalformedChar, got {:?}", other),
+        }
+
+        let too_long = b"'ab'".to_vec();
+        let mut lexer = Lexer::new(&*too_long).unwrap();
+        match lexer.next_token() {
+            Err(LexerError::MalformedChar(_)) => (),
+            other => panic!("expected MalformedChar, got {:?}", other),
+        }
+    }
+
+    #[test] fn multi_level_unindent() {
+        use self::Lexeme::*;
+        let nested = concat!(
+            "a:\n",
+            "    b:\n",
+            "        c\n",
+            "d\n"
+        ).as_bytes();
+
+        let mut lexer = Lexer::new(nested).unwrap();
+        let mut lexemes = collect_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Word("a".to_string()), Colon, Newline,
+            Indent(4), Word("b".to_string()), Colon, Newline,
+            Indent(4), Word("c".to_string()), Newline,
+            Unindent(4), Unindent(4), Word("d".to_string()), Newline,
+            Eof
+        ])
+    }
+
+    #[test] fn tab_error_on_ambiguous_indent() {
+        let mixed = concat!(
+            "a\n",
+            "    b\n",
+            "\tc\n"
+        ).as_bytes();
+
+        let mut lexer = Lexer::new(mixed).unwrap();
+        loop {
+            match lexer.next_token() {
+                Ok(ref tok) if tok.lexeme == Lexeme::Eof => panic!("expected TabError"),
+                Ok(_) => continue,
+                Err(LexerError::TabError(_)) => break,
+                Err(other) => panic!("expected TabError, got {:?}", other),
+            }
+        }
+    }
+
+    #[test] fn brackets_suppress_layout() {
+        use self::Lexeme::*;
+        let call = concat!(
+            "f(\n",
+            "    a,\n",
+            "    b\n",
+            ")\n"
+        ).as_bytes();
+
+        let mut lexer = Lexer::new(call).unwrap();
+        let mut lexemes = collect_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Word("f".to_string()), Lparren,
+            Word("a".to_string()), Comma,
+            Word("b".to_string()),
+            Rparren, Newline,
+            Eof
+        ])
+    }
+
+    #[test] fn lossy_resyncs_past_errors() {
+        use self::Lexeme::*;
+        let source = b"foo \x01 1.bar baz".to_vec();
+
+        let mut lexer = Lexer::new(&*source).unwrap();
+        let mut lexemes = vec![];
+        loop {
+            let tok = lexer.next_token_lossy();
+            let done = tok.lexeme == Eof;
+            lexemes.push(tok.lexeme);
+            if done { break }
+        }
+
+        assert_eq!(&*lexemes, &[
+            Word("foo".to_string()),
+            Error("\u{1}".to_string()),
+            Error("1.bar".to_string()), Word("baz".to_string()),
+            Eof
+        ])
+    }
+
+    #[test] fn lossy_preserves_malformed_numeric_text() {
+        use self::Lexeme::*;
+
+        let mut lexer = Lexer::new(&b"0xZZ baz"[..]).unwrap();
+        match lexer.next_token_lossy().lexeme {
+            Error(text) => assert_eq!(text, "0xZZ"),
+            other => panic!("expected Error, got {:?}", other),
+        }
+
+        let mut lexer = Lexer::new(&b"1e baz"[..]).unwrap();
+        match lexer.next_token_lossy().lexeme {
+            Error(text) => assert_eq!(text, "1e"),
+            other => panic!("expected Error, got {:?}", other),
+        }
+    }
+
+    #[test] fn numeric_literals() {
+        use self::Lexeme::*;
+        let source = concat!(
+            "0x1A_2b 0o17 0b1010_1010 1_000 3.14 2.5e10 1e-3\n"
+        ).as_bytes();
+
+        let mut lexer = Lexer::new(source).unwrap();
+        let mut lexemes = collect_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Integer("0x1A2b".to_string()),
+            Integer("0o17".to_string()),
+            Integer("0b10101010".to_string()),
+            Integer("1000".to_string()),
+            Float("3.14".to_string()),
+            Float("2.5e10".to_string()),
+            Float("1e-3".to_string()),
+            Newline, Eof
+        ])
+    }
+
+    #[test] fn invalid_radix_digit_errors() {
+        let bad = b"0b102".to_vec();
+        let mut lexer = Lexer::new(&*bad).unwrap();
+        match lexer.next_token() {
+            Err(LexerError::InvalidInteger(_, _)) => (),
+            other => panic!("expected InvalidInteger, got {:?}", other),
+        }
+    }
+
+    #[test] fn trailing_digit_separator_matches_its_span() {
+        use self::Lexeme::*;
+        let source = b"123_ baz".to_vec();
+
+        let mut lexer = Lexer::new(&*source).unwrap();
+        let tok = lexer.next_token().unwrap();
+
+        let width = tok.position.to.0 - tok.position.from.0;
+        match tok.lexeme {
+            Integer(text) => assert_eq!(text.len(), width),
+            other => panic!("expected Integer, got {:?}", other),
+        }
+    }
+
+    #[test] fn trailing_digit_separator_matches_its_span_in_radix_integer() {
+        use self::Lexeme::*;
+        let source = b"0x1_ baz".to_vec();
+
+        let mut lexer = Lexer::new(&*source).unwrap();
+        let tok = lexer.next_token().unwrap();
+
+        let width = tok.position.to.0 - tok.position.from.0;
+        match tok.lexeme {
+            Integer(text) => assert_eq!(text.len(), width),
+            other => panic!("expected Integer, got {:?}", other),
+        }
+    }
+
+    #[test] fn slice_lexer_hello() {
+        use self::SliceLexeme::*;
+        let hello = concat!(
+            "main =\n",
+            "    \"Hello, world!\" print_ln\n"
+        );
+
+        let mut lexer = SliceLexer::new(hello);
+        let lexemes = collect_slice_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Word("main"), Equals, Newline,
+            Indent(4), String("Hello, world!".to_string()), Word("print_ln"), Newline,
+            Unindent(4), Eof
+        ])
+    }
+
+    #[test] fn slice_lexer_numeric_literals_keep_separators() {
+        use self::SliceLexeme::*;
+        let source = "0x1A_2b 1_000 2.5e10\n";
+
+        let mut lexer = SliceLexer::new(source);
+        let lexemes = collect_slice_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Integer("0x1A_2b"),
+            Integer("1_000"),
+            Float("2.5e10"),
+            Newline, Eof
+        ])
+    }
+
+    #[test] fn slice_lexer_tab_error_on_ambiguous_indent() {
+        let mixed = concat!(
+            "a\n",
+            "    b\n",
+            "\tc\n"
+        );
+
+        let mut lexer = SliceLexer::new(mixed);
+        loop {
+            match lexer.next_token() {
+                Ok(ref tok) if tok.lexeme == SliceLexeme::Eof => panic!("expected TabError"),
+                Ok(_) => continue,
+                Err(SliceLexerError::TabError(_)) => break,
+                Err(other) => panic!("expected TabError, got {:?}", other),
+            }
+        }
+    }
+
+    #[test] fn slice_lexer_chars_and_escapes() {
+        use self::SliceLexeme::*;
+        let source = concat!(
+            "'a' '\\n' '\\x41' '\\u{1f600}'\n",
+            "\"tab\\there\\x21\\u{263a}\"\n"
+        );
+
+        let mut lexer = SliceLexer::new(source);
+        let lexemes = collect_slice_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Char('a'), Char('\n'), Char('\x41'), Char('\u{1f600}'), Newline,
+            String("tab\there!\u{263a}".to_string()), Newline,
+            Eof
+        ])
+    }
+
+    #[test] fn slice_lexer_malformed_char_errors() {
+        let empty = "''";
+        let mut lexer = SliceLexer::new(empty);
+        match lexer.next_token() {
+            Err(SliceLexerError::MalformedChar(_)) => (),
+            other => panic!("expected MalformedChar, got {:?}", other),
+        }
+
+        let too_long = "'ab'";
+        let mut lexer = SliceLexer::new(too_long);
+        match lexer.next_token() {
+            Err(SliceLexerError::MalformedChar(_)) => (),
+            other => panic!("expected MalformedChar, got {:?}", other),
+        }
+    }
+
+    #[test] fn slice_lexer_malformed_char_does_not_skip_following_char() {
+        let mut lexer = SliceLexer::new("'ab' baz");
+        match lexer.next_token() {
+            Err(SliceLexerError::MalformedChar(_)) => (),
+            other => panic!("expected MalformedChar, got {:?}", other),
+        }
+
+        match lexer.next_token().map(|tok| tok.lexeme) {
+            Ok(SliceLexeme::Word(w)) => assert_eq!(w, "b"),
+            other => panic!("expected Word(\"b\"), got {:?}", other),
+        }
+    }
+
+    #[test] fn slice_lexer_brackets_suppress_layout() {
+        use self::SliceLexeme::*;
+        let call = concat!(
+            "f(\n",
+            "    a,\n",
+            "    b\n",
+            ")\n"
+        );
+
+        let mut lexer = SliceLexer::new(call);
+        let lexemes = collect_slice_lexemes(&mut lexer);
+
+        assert_eq!(&*lexemes, &[
+            Word("f"), Lparren,
+            Word("a"), Comma,
+            Word("b"),
+            Rparren, Newline,
+            Eof
+        ])
+    }
 }